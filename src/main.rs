@@ -11,47 +11,193 @@
 // His python implementation calculates up to n=7 in about 40 seconds on my machine. This
 // rust implementation calculates up to n=7 in about 0.15 seconds on my machine.
 
-const MAX_N: usize = 7; // n=7 takes <1 second, n=8 takes ~24 hours
-const MAX_SUBSETS: usize = 1 << MAX_N; // The cardinality of the powerset of a set of size MAX_N
-const BITSET_WORDS: usize = MAX_SUBSETS / 64;
+/// A dense, domain-sized bitset in the style of rustc's `BitSet<T>`, where a 1 at index i means
+/// the subset with bitmask i is in the set system. `domain_size` is fixed at construction
+/// rather than compile time, so the same binary can compute any n without being recompiled.
+#[derive(Clone)]
+struct Bitset {
+    domain_size: usize,
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    /// Allocates a bitset over `domain_size` indices, all initially clear.
+    fn new_empty(domain_size: usize) -> Self {
+        let num_words = domain_size.div_ceil(64);
+        Bitset {
+            domain_size,
+            words: vec![0u64; num_words],
+        }
+    }
+
+    /// Sets all bits in the bitset to zero.
+    fn clear(&mut self) {
+        for word in self.words.iter_mut() {
+            *word = 0;
+        }
+    }
 
-/// A bitwise operable representation of the powerset, where 1 represents a valid Dynkin system.
-type Bitset = [u64; BITSET_WORDS];
+    /// Sets a specific bit in the bitset to 1.
+    fn insert(&mut self, index: usize) {
+        assert!(index < self.domain_size);
+        let word = index >> 6;
+        let bit = index & 63;
+        self.words[word] |= 1 << bit;
+    }
+
+    /// Gets the value of a specific bit in the bitset.
+    /// Returns true if the bit is set, false otherwise.
+    fn contains(&self, index: usize) -> bool {
+        assert!(index < self.domain_size);
+        let word = index >> 6;
+        let bit = index & 63;
+        ((self.words[word] >> bit) & 1) != 0
+    }
+
+    /// Copies the contents of one bitset into another. Both bitsets must share the same
+    /// domain size.
+    fn copy_from(&mut self, other: &Bitset) {
+        assert_eq!(self.domain_size, other.domain_size);
+        self.words.copy_from_slice(&other.words);
+    }
 
-/// Sets all bits in the bitset to zero.
-fn bs_clear(bitset: &mut Bitset) {
-    for word in bitset.iter_mut() {
-        *word = 0;
+    /// Iterates over the indices of set bits, low to high.
+    fn iter(&self) -> BitsetIter<'_> {
+        BitsetIter {
+            words: self.words.iter(),
+            base: 0,
+            current: 0,
+        }
+    }
+
+    /// Encodes the bitset as a compact byte blob (domain size, then the raw words,
+    /// little-endian), mirroring the `bit-set` crate's `to_bytes`/`from_bytes` pair so a
+    /// bitset can be written to and read back from a checkpoint file.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.words.len() * 8);
+        bytes.extend_from_slice(&(self.domain_size as u64).to_le_bytes());
+        for word in &self.words {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a bitset previously written by `to_bytes`.
+    fn from_bytes(bytes: &[u8]) -> Bitset {
+        let domain_size = u64::from_le_bytes(bytes[0..8].try_into().unwrap()) as usize;
+        let num_words = domain_size.div_ceil(64);
+        let mut words = Vec::with_capacity(num_words);
+        for i in 0..num_words {
+            let start = 8 + i * 8;
+            words.push(u64::from_le_bytes(
+                bytes[start..start + 8].try_into().unwrap(),
+            ));
+        }
+        Bitset { domain_size, words }
     }
 }
 
-/// Copies the contents of one bitset to another.
-fn bs_copy(destination: &mut Bitset, source: &Bitset) {
-    destination.copy_from_slice(source);
+/// Iterates over the set bits of a `Bitset`, in the style of the `bit-set` crate: scan words
+/// in order and peel off the lowest set bit with `trailing_zeros` until the word is exhausted,
+/// then advance to the next word.
+struct BitsetIter<'a> {
+    words: std::slice::Iter<'a, u64>,
+    base: usize,
+    current: u64,
 }
 
-/// Sets a specific bit in the bitset to 1.
-fn bs_set(bitset: &mut Bitset, index: usize) {
-    let word: usize = index >> 6;
-    let bit: usize = index & 63;
-    bitset[word] |= 1 << bit;
+impl Iterator for BitsetIter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.current = *self.words.next()?;
+            self.base += 64;
+        }
+        let lsb = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.base - 64 + lsb)
+    }
+}
+
+/// Bulk, word-at-a-time set operations between two same-sized bitsets, in the style of
+/// rustc_index's `BitRelations`. Each method reports whether `self` actually changed, so
+/// callers can tell a no-op union/intersect/subtract apart from one that found something new
+/// without a separate membership test.
+trait BitRelations {
+    /// `self = self ∪ other`. Returns true if any bit was newly set.
+    fn union(&mut self, other: &Bitset) -> bool;
+    /// `self = self ∖ other`. Returns true if any bit was cleared.
+    fn subtract(&mut self, other: &Bitset) -> bool;
+    /// `self = self ∩ other`. Returns true if any bit was cleared.
+    fn intersect(&mut self, other: &Bitset) -> bool;
+}
+
+impl BitRelations for Bitset {
+    fn union(&mut self, other: &Bitset) -> bool {
+        assert_eq!(self.domain_size, other.domain_size);
+        let mut changed = false;
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            let merged = *word | other_word;
+            changed |= merged != *word;
+            *word = merged;
+        }
+        changed
+    }
+
+    fn subtract(&mut self, other: &Bitset) -> bool {
+        assert_eq!(self.domain_size, other.domain_size);
+        let mut changed = false;
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            let remaining = *word & !other_word;
+            changed |= remaining != *word;
+            *word = remaining;
+        }
+        changed
+    }
+
+    fn intersect(&mut self, other: &Bitset) -> bool {
+        assert_eq!(self.domain_size, other.domain_size);
+        let mut changed = false;
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            let kept = *word & other_word;
+            changed |= kept != *word;
+            *word = kept;
+        }
+        changed
+    }
 }
 
-/// Gets the value of a specific bit in the bitset.
-/// Returns true if the bit is set, false otherwise.
-fn bs_get(bitset: &Bitset, index: usize) -> bool {
-    let word: usize = index >> 6;
-    let bit: usize = index & 63;
-    ((bitset[word] >> bit) & 1) != 0
+/// Precomputes, for every `x` in the domain, the bitset of `y` with `x & y == 0` (the subsets
+/// disjoint from `x`). This answer depends only on the domain size, not on the current
+/// closure, so it is computed once per n and shared by every call to `extend_closure`.
+fn compute_disjoint_masks(domain_size: usize) -> Vec<Bitset> {
+    let mut masks = vec![Bitset::new_empty(domain_size); domain_size];
+    for (x, mask) in masks.iter_mut().enumerate() {
+        for y in 0..domain_size {
+            if x & y == 0 {
+                mask.insert(y);
+            }
+        }
+    }
+    masks
 }
 
 /// A queue to manage the elements being processed during the closure extension.
 struct Queue {
-    data: [usize; MAX_SUBSETS],
+    data: Vec<usize>,
     len: usize,
 }
 
 impl Queue {
+    /// Allocates a queue large enough to hold every index in `domain_size`.
+    fn new(domain_size: usize) -> Self {
+        Queue {
+            data: vec![0; domain_size],
+            len: 0,
+        }
+    }
+
     fn clear(&mut self) {
         self.len = 0;
     }
@@ -70,7 +216,9 @@ impl Queue {
 /// - `extension`: the new element to be included in the closure
 /// - `excluded`: the current set of excluded elements (elements that cannot be included)
 /// - `closure`: the current closure set to be updated
+/// - `disjoint_masks`: precomputed disjoint-subset masks for this domain, see `compute_disjoint_masks`
 /// - `queue`: a queue to manage the elements being processed
+///
 /// Returns true if the extension is valid, false otherwise.
 fn extend_closure(
     omega: usize,
@@ -78,51 +226,83 @@ fn extend_closure(
     extension: usize,
     excluded: &Bitset,
     closure: &mut Bitset,
+    disjoint_masks: &[Bitset],
     queue: &mut Queue,
 ) -> bool {
-    bs_copy(closure, included);
-    bs_set(closure, extension);
+    let domain_size = closure.domain_size;
+    closure.copy_from(included);
+    closure.insert(extension);
     queue.clear();
     queue.push(extension);
     let mut queue_index: usize = 0;
 
+    // Scratch bitsets reused across every element popped from the queue, so a single call to
+    // `extend_closure` allocates each buffer once rather than once per queue step.
+    let mut candidates = Bitset::new_empty(domain_size);
+    let mut reachable = Bitset::new_empty(domain_size);
+    let mut newly_added = Bitset::new_empty(domain_size);
+
     while queue_index < queue.len {
         let x: usize = queue.data[queue_index];
         queue_index += 1;
         let complement: usize = omega ^ x;
-        if !bs_get(closure, complement) {
-            if bs_get(excluded, complement) {
+        if !closure.contains(complement) {
+            if excluded.contains(complement) {
                 return false;
             }
-            bs_set(closure, complement);
+            closure.insert(complement);
             queue.push(complement);
         }
 
-        for word in 0..BITSET_WORDS {
-            let mut bits: u64 = closure[word];
-
-            while bits != 0 {
-                let lsb: usize = bits.trailing_zeros() as usize;
-                bits &= bits - 1;
-                let y: usize = (word << 6) + lsb; // Calculates the index of the bit
-
-                // If x ⋂ y = ∅, then we can add x ⋃ y to the closure
-                if (x & y) == 0 {
-                    let z: usize = x | y;
-                    if !bs_get(closure, z) {
-                        if bs_get(excluded, z) {
-                            return false;
-                        }
-                        bs_set(closure, z);
-                        queue.push(z);
-                    }
-                }
+        // The y already in the closure that are disjoint from x: one word-at-a-time
+        // intersection against the precomputed mask for x, instead of testing
+        // `x & y == 0` bit-by-bit on every element of the closure.
+        candidates.copy_from(closure);
+        candidates.intersect(&disjoint_masks[x]);
+
+        reachable.clear();
+        for y in candidates.iter() {
+            reachable.insert(x | y); // x ⋂ y = ∅, so x ⋃ y belongs in the closure
+        }
+
+        // Only genuinely new subsets need to be checked against `excluded`: `closure` and
+        // `excluded` legitimately overlap (a forced complement is inserted into both), so
+        // testing `reachable` directly would prune branches that merely re-derive an
+        // already-included element.
+        newly_added.copy_from(&reachable);
+        newly_added.subtract(closure);
+
+        for word in 0..newly_added.words.len() {
+            if newly_added.words[word] & excluded.words[word] != 0 {
+                return false;
+            }
+        }
+
+        if closure.union(&reachable) {
+            for z in newly_added.iter() {
+                queue.push(z);
             }
         }
     }
     true
 }
 
+/// Key identifying a subtree of the `inner` recursion for memoization purposes. Two calls with
+/// the same `lower_bound`, `included`, and `excluded` always return the same count, but all
+/// three fields are required: two paths can reach an identical `included` closure while still
+/// disagreeing on which elements remain excluded or on where the remaining candidates start,
+/// and those differences change the count.
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct CacheKey {
+    lower_bound: usize,
+    included: Vec<u64>,
+    excluded: Vec<u64>,
+}
+
+/// Maps a `CacheKey` to the previously computed subtree count, hashed with ahash rather than
+/// the default SipHash for lookup speed on this very hot path.
+type SubtreeCache = ahash::AHashMap<CacheKey, usize>;
+
 /// Recursively counts the number of valid subsets of the closure.
 /// Given a set system F and a property P, the closure of F with respect to P is the smallest superset of F satisfying P.
 /// Parameters:
@@ -130,100 +310,393 @@ fn extend_closure(
 /// - `lower_bound`: the lower bound for the next element to consider
 /// - `included`: the current set of included elements (the closure)
 /// - `excluded`: the current set of excluded elements (elements that cannot be included)
-/// - `queue`: a queue to manage the elements being processed
+/// - `disjoint_masks`: precomputed disjoint-subset masks for this domain, see `compute_disjoint_masks`
+/// - `on_system`: when set, called with every fully-determined Dynkin system found, i.e. every
+///   `included` that the count below treats as a complete, valid system
+/// - `cache`: memoized subtree counts, keyed on `(lower_bound, included, excluded)`; bypassed
+///   while `on_system` is set, since a cache hit would skip emitting the systems underneath it
+///
 /// Returns the count of valid subsets of the closure.
 fn inner(
     omega: usize,
     lower_bound: usize,
     included: &Bitset,
     excluded: &mut Bitset,
-    _queue: &mut Queue,
-    depth: usize,
+    disjoint_masks: &[Bitset],
+    on_system: Option<&(dyn Fn(&Bitset) + Sync)>,
+    cache: &mut SubtreeCache,
 ) -> usize {
+    let cache_key = on_system.is_none().then(|| CacheKey {
+        lower_bound,
+        included: included.words.clone(),
+        excluded: excluded.words.clone(),
+    });
+    if let Some(key) = &cache_key {
+        if let Some(&cached) = cache.get(key) {
+            return cached;
+        }
+    }
+
     let mut count: usize = 1;
     let limit: usize = (omega + 1) >> 1;
+    let domain_size: usize = omega + 1;
 
-    // local queue for closure extension
-    let mut queue_local: Queue = Queue {
-        data: [0; MAX_SUBSETS],
-        len: 0,
-    };
+    if let Some(emit) = on_system {
+        emit(included);
+    }
+
+    // Local closure/queue buffers, allocated once per call and reused (via clear()) across
+    // every `x` considered at this recursion level to avoid heap churn.
+    let mut closure = Bitset::new_empty(domain_size);
+    let mut queue_local = Queue::new(domain_size);
 
     for x in lower_bound..limit {
-        if bs_get(included, x) || bs_get(excluded, x) {
+        if included.contains(x) || excluded.contains(x) {
             continue;
         }
 
         // Inclusion branch
-        let mut closure = [0u64; BITSET_WORDS];
+        closure.clear();
         queue_local.clear();
-        if extend_closure(omega, included, x, excluded, &mut closure, &mut queue_local) {
-            let mut new_excluded = *excluded;
+        if extend_closure(
+            omega,
+            included,
+            x,
+            excluded,
+            &mut closure,
+            disjoint_masks,
+            &mut queue_local,
+        ) {
+            let mut new_excluded = excluded.clone();
             count += inner(
                 omega,
                 x + 1,
                 &closure,
                 &mut new_excluded,
-                &mut queue_local,
-                depth,
+                disjoint_masks,
+                on_system,
+                cache,
             );
         }
 
         // Exclusion branch
-        bs_set(excluded, x);
-        bs_set(excluded, omega ^ x); // D-x
+        excluded.insert(x);
+        excluded.insert(omega ^ x); // D-x
+    }
+
+    if let Some(key) = cache_key {
+        cache.insert(key, count);
     }
 
     count
 }
 
-fn main() {
-    // Calculate number of Dynkin systems for each set size
-    for n in 0..=MAX_N {
-        let omega: usize = if n > 0 { (1 << n) - 1 } else { 0 };
-
-        // Initial included bitset: {∅, X}
-        let mut included = [0u64; BITSET_WORDS];
-        bs_clear(&mut included);
-        bs_set(&mut included, 0);
-        bs_set(&mut included, omega);
-
-        // Prepare root_excluded array up to halfway
-        let limit = (omega + 1) >> 1;
-        let mut root_excluded = vec![[0u64; BITSET_WORDS]; limit];
-        for m in 1..limit {
-            root_excluded[m] = root_excluded[m - 1];
-            bs_set(&mut root_excluded[m], m);
-            bs_set(&mut root_excluded[m], omega ^ m);
-        }
-
-        // Parallel over m choices
-        use rayon::prelude::*;
-        let sum: usize = (1..limit)
-            .into_par_iter()
-            .map(|m| {
-                if bs_get(&included, m) || bs_get(&root_excluded[m - 1], m) {
-                    return 0;
+/// Formats a single subset (given as a bitmask over the base elements 0..n) as e.g. `{1,2}`,
+/// or `∅` for the empty set.
+fn format_subset(subset: usize) -> String {
+    if subset == 0 {
+        return "∅".to_string();
+    }
+    let members: Vec<String> = (0..usize::BITS as usize)
+        .filter(|bit| subset & (1 << bit) != 0)
+        .map(|bit| bit.to_string())
+        .collect();
+    format!("{{{}}}", members.join(","))
+}
+
+/// Formats a whole Dynkin system (a bitset of subset indices) as e.g. `{∅, {0}, {1,2}}`.
+fn format_system(system: &Bitset) -> String {
+    let subsets: Vec<String> = system.iter().map(format_subset).collect();
+    format!("{{{}}}", subsets.join(", "))
+}
+
+/// Fingerprints the run parameters a checkpoint file was produced for, so a checkpoint left
+/// over from a different `n` (whether or not its word count differs) is never mistaken for one
+/// that can be resumed.
+fn checkpoint_fingerprint(n: usize, domain_size: usize) -> u64 {
+    const SALT: u64 = 0x44594e4b494e3100; // arbitrary tag, spells roughly "DYNKIN" in ASCII
+    let num_words = domain_size.div_ceil(64);
+    SALT ^ (n as u64) ^ ((num_words as u64) << 32)
+}
+
+/// Loads the `m -> count` pairs already recorded in a checkpoint file, provided its fingerprint
+/// matches the current run. A missing or unreadable file, or a fingerprint mismatch (a
+/// checkpoint from a different n), is treated as "nothing completed yet" rather than an error.
+/// Reading stops at a truncated trailing record or at a closure encoded for a different domain
+/// size; the records read before it are kept.
+fn load_checkpoint(
+    path: &std::path::Path,
+    fingerprint: u64,
+    domain_size: usize,
+) -> ahash::AHashMap<usize, usize> {
+    use std::io::Read;
+
+    let mut completed = ahash::AHashMap::default();
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return completed;
+    };
+    let mut bytes = Vec::new();
+    if file.read_to_end(&mut bytes).is_err() || bytes.len() < 8 {
+        return completed;
+    }
+    if u64::from_le_bytes(bytes[0..8].try_into().unwrap()) != fingerprint {
+        return completed;
+    }
+
+    let mut offset = 8;
+    while offset + 24 <= bytes.len() {
+        let m = u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap()) as usize;
+        let count = u64::from_le_bytes(bytes[offset + 8..offset + 16].try_into().unwrap()) as usize;
+        let bitset_len =
+            u64::from_le_bytes(bytes[offset + 16..offset + 24].try_into().unwrap()) as usize;
+        offset += 24;
+        if offset + bitset_len > bytes.len() {
+            break; // Trailing partial record from a run that was killed mid-write; stop here.
+        }
+        let closure = Bitset::from_bytes(&bytes[offset..offset + bitset_len]);
+        offset += bitset_len;
+        if closure.domain_size != domain_size {
+            break; // Closure was encoded for a different n; the rest of the file can't be trusted.
+        }
+        completed.insert(m, count);
+    }
+    completed
+}
+
+/// Opens the checkpoint file for appending new records: fresh (truncated, with a new
+/// fingerprint header) if nothing is being resumed, or appended to otherwise.
+fn open_checkpoint_writer(
+    path: &std::path::Path,
+    fingerprint: u64,
+    resuming: bool,
+) -> std::io::Result<std::fs::File> {
+    use std::io::Write;
+
+    if resuming {
+        std::fs::OpenOptions::new().append(true).open(path)
+    } else {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        file.write_all(&fingerprint.to_le_bytes())?;
+        Ok(file)
+    }
+}
+
+/// Appends one completed root branch to the checkpoint file and flushes immediately, so a
+/// crash or reboot loses at most the branch currently in flight.
+fn append_checkpoint_record(
+    writer: &std::sync::Mutex<std::io::BufWriter<std::fs::File>>,
+    m: usize,
+    count: usize,
+    closure: &Bitset,
+) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let bytes = closure.to_bytes();
+    let mut writer = writer.lock().unwrap();
+    writer.write_all(&(m as u64).to_le_bytes())?;
+    writer.write_all(&(count as u64).to_le_bytes())?;
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()
+}
+
+/// Counts the Dynkin systems on a set of size `n`, optionally calling `on_system` with every
+/// system found along the way (see `inner`), and optionally resuming from / checkpointing to
+/// `checkpoint_path` so a multi-day run survives a crash or reboot.
+fn count_systems(
+    n: usize,
+    on_system: Option<&(dyn Fn(&Bitset) + Sync)>,
+    checkpoint_path: Option<&std::path::Path>,
+) -> usize {
+    // Checkpointing only skips/records whole branches, never the individual systems inside
+    // them, so it is incompatible with `--enumerate`: a resumed branch would silently never
+    // have its systems re-emitted. Treat the two as mutually exclusive by ignoring the
+    // checkpoint path entirely whenever `on_system` is set.
+    let checkpoint_path = checkpoint_path.filter(|_| on_system.is_none());
+
+    let omega: usize = if n > 0 { (1 << n) - 1 } else { 0 };
+    let domain_size: usize = omega + 1;
+    let disjoint_masks = compute_disjoint_masks(domain_size);
+
+    // Initial included bitset: {∅, X}
+    let mut included = Bitset::new_empty(domain_size);
+    included.insert(0);
+    included.insert(omega);
+    if let Some(emit) = on_system {
+        emit(&included);
+    }
+
+    // Prepare root_excluded array up to halfway
+    let limit = (omega + 1) >> 1;
+    let mut root_excluded = vec![Bitset::new_empty(domain_size); limit];
+    for m in 1..limit {
+        let (prev, rest) = root_excluded.split_at_mut(m);
+        rest[0].copy_from(&prev[m - 1]);
+        rest[0].insert(m);
+        rest[0].insert(omega ^ m);
+    }
+
+    // Resume from a checkpoint, if one was requested and one exists for this n. Branches
+    // already recorded are skipped below instead of being recomputed.
+    let fingerprint = checkpoint_path.map(|_| checkpoint_fingerprint(n, domain_size));
+    let completed = match (checkpoint_path, fingerprint) {
+        (Some(path), Some(fp)) => load_checkpoint(path, fp, domain_size),
+        _ => ahash::AHashMap::default(),
+    };
+    let writer =
+        checkpoint_path.zip(fingerprint).and_then(|(path, fp)| {
+            match open_checkpoint_writer(path, fp, !completed.is_empty()) {
+                Ok(file) => Some(std::sync::Mutex::new(std::io::BufWriter::new(file))),
+                Err(err) => {
+                    eprintln!(
+                        "warning: could not open checkpoint file {}: {}",
+                        path.display(),
+                        err
+                    );
+                    None
                 }
-                // Build closure and count branch
-                let mut closure = [0u64; BITSET_WORDS];
-                let mut queue: Queue = Queue {
-                    data: [0; MAX_SUBSETS],
-                    len: 0,
-                };
-                let ex_here = &root_excluded[m - 1];
-                let mut count: usize = 0;
-                if extend_closure(omega, &included, m, ex_here, &mut closure, &mut queue) {
-                    let mut new_exc = *ex_here;
-                    bs_set(&mut new_exc, m);
-                    bs_set(&mut new_exc, omega ^ m);
-                    count += inner(omega, m + 1, &closure, &mut new_exc, &mut queue, 0);
+            }
+        });
+
+    // Parallel over m choices. Each rayon worker gets its own subtree cache via `map_init`,
+    // since lower_bound is part of `CacheKey` there is no cross-branch collision risk, and
+    // reusing a thread's cache across the branches it picks up needs no merge step at the end
+    // because only the per-branch counts (not the caches) feed into the final sum.
+    use rayon::prelude::*;
+    let sum: usize = (1..limit)
+        .into_par_iter()
+        .map_init(SubtreeCache::default, |cache, m| {
+            if included.contains(m) || root_excluded[m - 1].contains(m) {
+                return 0;
+            }
+            if let Some(&count) = completed.get(&m) {
+                return count;
+            }
+            // Build closure and count branch
+            let mut closure = Bitset::new_empty(domain_size);
+            let mut queue: Queue = Queue::new(domain_size);
+            let ex_here = &root_excluded[m - 1];
+            let mut count: usize = 0;
+            if extend_closure(
+                omega,
+                &included,
+                m,
+                ex_here,
+                &mut closure,
+                &disjoint_masks,
+                &mut queue,
+            ) {
+                let mut new_exc = ex_here.clone();
+                new_exc.insert(m);
+                new_exc.insert(omega ^ m);
+                count += inner(
+                    omega,
+                    m + 1,
+                    &closure,
+                    &mut new_exc,
+                    &disjoint_masks,
+                    on_system,
+                    cache,
+                );
+            } else {
+                // No valid subtree under this m; keep the checkpoint record's bitset a
+                // well-defined empty placeholder rather than extend_closure's abandoned state.
+                closure.clear();
+            }
+            if let Some(writer) = &writer {
+                if let Err(err) = append_checkpoint_record(writer, m, count, &closure) {
+                    eprintln!(
+                        "warning: failed to append checkpoint record for m={}: {}",
+                        m, err
+                    );
                 }
-                count
-            })
-            .sum();
+            }
+            count
+        })
+        .sum();
+
+    sum + 1
+}
+
+/// Parsed command-line arguments.
+struct Args {
+    /// The largest set size to compute, inclusive.
+    n: usize,
+    /// Whether to print every individual Dynkin system found for `n`, not just the count.
+    enumerate: bool,
+    /// Where to resume from / checkpoint to while computing `n`. Earlier, fast set sizes in
+    /// the `0..=n` sweep are never checkpointed, only the expensive target `n` itself. Ignored
+    /// if `enumerate` is set, since checkpointing only skips/records whole branches and cannot
+    /// replay the individual systems inside a resumed one.
+    checkpoint: Option<std::path::PathBuf>,
+}
+
+/// Reads the target set size `n`, and the optional `--enumerate` and `--checkpoint <path>`
+/// flags, from the command line.
+fn parse_args() -> Args {
+    let mut args = std::env::args();
+    let program = args
+        .next()
+        .unwrap_or_else(|| "count-finite-lambda-systems".to_string());
+    let mut n = None;
+    let mut enumerate = false;
+    let mut checkpoint = None;
+    while let Some(arg) = args.next() {
+        if arg == "--enumerate" {
+            enumerate = true;
+        } else if arg == "--checkpoint" {
+            checkpoint = args.next().map(std::path::PathBuf::from);
+        } else if let Ok(value) = arg.parse::<usize>() {
+            n = Some(value);
+        }
+    }
+    match n {
+        Some(n) => Args {
+            n,
+            enumerate,
+            checkpoint,
+        },
+        None => {
+            eprintln!("Usage: {} <n> [--enumerate] [--checkpoint <path>]", program);
+            std::process::exit(1);
+        }
+    }
+}
 
-        let total: usize = sum + 1;
-        println!("{} -> {}", n, total);
+fn main() {
+    let args = parse_args();
+
+    if args.enumerate && args.checkpoint.is_some() {
+        eprintln!(
+            "warning: --enumerate and --checkpoint are mutually exclusive; ignoring --checkpoint"
+        );
+    }
+
+    // Calculate number of Dynkin systems for each set size
+    for n in 0..=args.n {
+        let is_target = n == args.n;
+        let checkpoint_path = if is_target {
+            args.checkpoint.as_deref()
+        } else {
+            None
+        };
+
+        if args.enumerate && is_target {
+            use std::sync::Mutex;
+            let systems: Mutex<Vec<Bitset>> = Mutex::new(Vec::new());
+            let on_system = |system: &Bitset| systems.lock().unwrap().push(system.clone());
+            let total = count_systems(n, Some(&on_system), checkpoint_path);
+            println!("{} -> {}", n, total);
+            for system in systems.into_inner().unwrap() {
+                println!("  {}", format_system(&system));
+            }
+        } else {
+            let total = count_systems(n, None, checkpoint_path);
+            println!("{} -> {}", n, total);
+        }
     }
 }